@@ -1,10 +1,15 @@
 use serde::{ Deserialize, Serialize };
 use std::{
-    collections::HashMap, 
-    io::{ self, Read, Write }, 
+    collections::{ HashMap, HashSet },
+    io::{ self, Read, Write, Seek, SeekFrom },
     thread,
-    fs, 
-    borrow::Cow
+    fs,
+    borrow::Cow,
+    sync::{
+        Arc, Mutex,
+        atomic::{ AtomicU32, Ordering },
+        mpsc::{ self, Sender }
+    }
 };
 use byteorder::{ WriteBytesExt, ReadBytesExt, LittleEndian };
 use winit::{
@@ -25,7 +30,11 @@ struct WindowOptions {
     resizable: bool,
     maximized: bool,
     title: String,
-    preload: Option<String>
+    preload: Option<String>,
+    host_handled: bool,
+    allowed_origins: Vec<String>,
+    asset_bundle: Option<String>,
+    csp: Option<String>
 }
 
 #[derive(Debug, Deserialize)]
@@ -35,6 +44,8 @@ enum Request {
     LoadUrl { id: u32, url: String },
     LoadHtml { id: u32, html: String },
     EmitToWebview { id: u32, channel: String, payload: String },
+    ProtocolResponse { request_id: u32, status: u16, headers: HashMap<String, String>, body: Vec<u8> },
+    EvaluateScript { id: u32, request_id: u32, script: String },
     Exit
 }
 
@@ -45,7 +56,140 @@ enum EventOut {
     WindowCreated { id: u32 },
     WindowClosed { id: u32 },
     ResponseOk { id: u32 },
-    IPCMessage { id: u32, body: String }
+    IPCMessage { id: u32, body: String },
+    ProtocolRequest { id: u32, request_id: u32, method: String, uri: String, headers: HashMap<String, String>, body: Vec<u8> },
+    FileDropHover { id: u32, paths: Vec<String> },
+    FileDrop { id: u32, paths: Vec<String> },
+    FileDropCancelled { id: u32 },
+    IPCBlocked { id: u32, origin: String },
+    WindowResized { id: u32, width: u32, height: u32 },
+    WindowMoved { id: u32, x: i32, y: i32 },
+    WindowFocused { id: u32, focused: bool },
+    ScaleFactorChanged { id: u32, scale: f64 },
+    ScriptResult { id: u32, request_id: u32, result: ScriptOutcome }
+}
+
+// The outcome of a `Request::EvaluateScript` call, mirroring the `{ ok, value | error }`
+// envelope the JS shim posts back over the reserved script-result IPC channel.
+#[derive(Debug, Serialize)]
+struct ScriptOutcome {
+    ok: bool,
+    value: Option<serde_json::Value>,
+    error: Option<String>
+}
+
+// Channel name reserved for `EvaluateScript` replies; the IPC handler intercepts
+// messages posted on it instead of forwarding them as a normal `IPCMessage`.
+const SCRIPT_RESULT_CHANNEL: &str = "__deno_script_result";
+
+// Wraps a user expression so its result (or thrown error) is posted back over
+// the reserved script-result IPC channel, keyed by `request_id`.
+fn build_script_shim(request_id: u32, script: &str) -> String {
+    format!(
+        r#"(function() {{
+            // __send must never throw: a non-serializable eval result (a DOM
+            // node, `window`, a circular object, a BigInt) would otherwise take
+            // down JSON.stringify and leave the pending EvaluateScript call
+            // hanging forever on the host side.
+            function __send(payload) {{
+                try {{
+                    window.ipc.postMessage(JSON.stringify(payload));
+                }} catch (e) {{
+                    window.ipc.postMessage(JSON.stringify({{ channel: "{channel}", request_id: {request_id}, ok: false, error: "unable to serialize script result: " + String(e) }}));
+                }}
+            }}
+            try {{
+                const __value = (function() {{ return ({script}); }})();
+                __send({{ channel: "{channel}", request_id: {request_id}, ok: true, value: __value }});
+            }} catch (e) {{
+                __send({{ channel: "{channel}", request_id: {request_id}, ok: false, error: String(e) }});
+            }}
+        }})();"#,
+        script = script,
+        channel = SCRIPT_RESULT_CHANNEL,
+        request_id = request_id
+    )
+}
+
+// Extracts the `scheme://authority` origin from a URL, e.g. "app:///index.html" → "app://".
+fn origin_of(url: &str) -> String {
+    match url.find("://") {
+        Some(scheme_end) => {
+            let after_scheme = &url[scheme_end + 3..];
+            let authority_end = after_scheme.find('/').unwrap_or(after_scheme.len());
+            format!("{}{}", &url[..scheme_end + 3], &after_scheme[..authority_end])
+        }
+        None => url.to_string()
+    }
+}
+
+// A resolved host-handled protocol response, sent back from Deno via
+// `Request::ProtocolResponse` and delivered to the blocked protocol closure.
+struct ProtocolResponsePayload {
+    status: u16,
+    headers: HashMap<String, String>,
+    body: Vec<u8>
+}
+
+// Generates a fresh per-load CSP nonce, hex-encoded so it drops straight into a `'nonce-…'` token.
+fn generate_nonce() -> String {
+    format!("{:032x}", rand::random::<u128>())
+}
+
+// Adds a `nonce="…"` attribute to every inline `<script` and `<style` tag so it keeps
+// running under a CSP whose `script-src`/`style-src` are pinned to that nonce.
+fn inject_nonce_into_html(html: &str, nonce: &str) -> String {
+    let mut result = String::with_capacity(html.len());
+    let mut rest = html;
+
+    loop {
+        let next = ["<script", "<style"]
+            .iter()
+            .filter_map(|tag| rest.find(tag).map(|pos| (pos, *tag)))
+            .min_by_key(|(pos, _)| *pos);
+
+        match next {
+            Some((pos, tag)) => {
+                result.push_str(&rest[..pos]);
+                let after_tag = rest[pos + tag.len()..].chars().next();
+                result.push_str(tag);
+                if matches!(after_tag, Some(' ') | Some('>') | Some('\t') | Some('\n')) {
+                    result.push_str(&format!(" nonce=\"{}\"", nonce));
+                }
+                rest = &rest[pos + tag.len()..];
+            }
+            None => {
+                result.push_str(rest);
+                break;
+            }
+        }
+    }
+
+    result
+}
+
+// Injects the rendered CSP as a `<meta http-equiv="Content-Security-Policy">` tag so
+// `load_html`, which has no HTTP response to attach a header to, still delivers the
+// policy to the engine. Creates a `<head>` if the document doesn't have one.
+fn inject_csp_meta(html: &str, csp: &str) -> String {
+    let meta_tag = format!(
+        "<meta http-equiv=\"Content-Security-Policy\" content=\"{}\">",
+        csp.replace('"', "&quot;")
+    );
+
+    if let Some(head_pos) = html.find("<head>") {
+        let insert_at = head_pos + "<head>".len();
+        return format!("{}{}{}", &html[..insert_at], meta_tag, &html[insert_at..]);
+    }
+
+    if let Some(html_pos) = html.find("<html") {
+        if let Some(tag_end) = html[html_pos..].find('>') {
+            let insert_at = html_pos + tag_end + 1;
+            return format!("{}<head>{}</head>{}", &html[..insert_at], meta_tag, &html[insert_at..]);
+        }
+    }
+
+    format!("<head>{}</head>{}", meta_tag, html)
 }
 
 fn send_event(ev: &EventOut) {
@@ -56,6 +200,35 @@ fn send_event(ev: &EventOut) {
     stdout.flush().unwrap();
 }
 
+// Parses a `Range: bytes=START-END` header against a known file length.
+// Returns the clamped (start, end) byte range, or `Err` if it is unsatisfiable.
+fn parse_range(header: &str, file_len: u64) -> Result<(u64, u64), ()> {
+    let spec = header.strip_prefix("bytes=").ok_or(())?;
+    let mut parts = spec.splitn(2, '-');
+    let start: u64 = parts.next().ok_or(())?.parse().map_err(|_| ())?;
+    let end = match parts.next() {
+        Some("") | None => file_len.saturating_sub(1),
+        Some(end_str) => end_str.parse::<u64>().map_err(|_| ())?,
+    };
+
+    if start >= file_len || end < start {
+        return Err(());
+    }
+
+    Ok((start, end.min(file_len.saturating_sub(1))))
+}
+
+// Reads `len` bytes starting at `start` from the file at `path`, opening and
+// seeking fresh each call so a file deleted/truncated between the earlier
+// `fs::metadata` check and this read surfaces as an `Err` instead of a panic.
+fn read_range(path: &str, start: u64, len: usize) -> io::Result<Vec<u8>> {
+    let mut file = fs::File::open(path)?;
+    file.seek(SeekFrom::Start(start))?;
+    let mut buf = vec![0u8; len];
+    file.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
 fn mime_type(path: &str) -> &'static str {
     match path.split('.').last().unwrap_or("") {
         "html" => "text/html",
@@ -67,10 +240,19 @@ fn mime_type(path: &str) -> &'static str {
         _ => "text/plain",
     }
 }
+// Maps a request path to its stored MIME type and Brotli-compressed bytes.
+type AssetBundle = HashMap<String, (String, Vec<u8>)>;
+
 struct App {
     proxy: EventLoopProxy<Request>,
     windows: HashMap<u32, (Window, WebView)>,
-    window_id_map: HashMap<WindowId, u32>
+    window_id_map: HashMap<WindowId, u32>,
+    pending_protocol_requests: Arc<Mutex<HashMap<u32, (u32, Sender<ProtocolResponsePayload>)>>>,
+    next_protocol_request_id: Arc<AtomicU32>,
+    window_origins: HashMap<u32, Arc<Mutex<String>>>,
+    window_csp_templates: HashMap<u32, Option<String>>,
+    window_csp: HashMap<u32, Arc<Mutex<Option<String>>>>,
+    pending_script_requests: Arc<Mutex<HashSet<(u32, u32)>>>
 }
 
 impl ApplicationHandler<Request> for App {
@@ -89,9 +271,142 @@ impl ApplicationHandler<Request> for App {
                 
                 let window: Window = event_loop.create_window(attrs).unwrap();
 
+                let host_handled = options.host_handled;
+                let pending_protocol_requests = self.pending_protocol_requests.clone();
+                let next_protocol_request_id = self.next_protocol_request_id.clone();
+                let allowed_origins = options.allowed_origins;
+                let origin = Arc::new(Mutex::new("app://".to_string()));
+                self.window_origins.insert(id, origin.clone());
+                let pending_script_requests = self.pending_script_requests.clone();
+
+                // Only the protocol closure needs the bundle; it isn't part of
+                // any other per-window state, so there's no reason to keep it
+                // around in `self.windows` once the closure has its own clone.
+                let asset_bundle_for_protocol: Arc<AssetBundle> = Arc::new(match &options.asset_bundle {
+                    Some(path) => {
+                        let bytes = fs::read(path).expect("Failed to read asset bundle");
+                        rmp_serde::from_slice(&bytes).expect("Failed to parse asset bundle")
+                    }
+                    None => HashMap::new()
+                });
+
+                self.window_csp_templates.insert(id, options.csp.clone());
+                let csp_state = Arc::new(Mutex::new(None));
+                self.window_csp.insert(id, csp_state.clone());
+                let csp_state_for_protocol = csp_state.clone();
+
                 let mut builder: WebViewBuilder = wry::WebViewBuilder::new()
                 .with_https_scheme(true)
-                .with_custom_protocol("app".to_string(), move |_id, request| {
+                .with_asynchronous_custom_protocol("app".to_string(), move |request, responder| {
+                    let csp_header = csp_state_for_protocol.lock().unwrap().clone();
+
+                    if host_handled {
+                        let request_id = next_protocol_request_id.fetch_add(1, Ordering::SeqCst);
+                        let (tx, rx) = mpsc::channel();
+                        pending_protocol_requests.lock().unwrap().insert(request_id, (id, tx));
+
+                        let headers: HashMap<String, String> = request.headers()
+                            .iter()
+                            .map(|(name, value)| (name.to_string(), value.to_str().unwrap_or_default().to_string()))
+                            .collect();
+
+                        send_event(&EventOut::ProtocolRequest {
+                            id,
+                            request_id,
+                            method: request.method().to_string(),
+                            uri: request.uri().path().to_string(),
+                            headers,
+                            body: request.body().to_vec()
+                        });
+
+                        // The reply only arrives once Deno's `ProtocolResponse` is processed on the
+                        // event loop, so wait for it off the protocol-handler thread instead of
+                        // blocking it (wry may invoke this closure on the UI thread).
+                        let pending_protocol_requests = pending_protocol_requests.clone();
+                        thread::spawn(move || {
+                            let response = match rx.recv() {
+                                Ok(response) => {
+                                    let mut builder = Response::builder().status(response.status);
+                                    for (name, value) in response.headers {
+                                        builder = builder.header(name, value);
+                                    }
+                                    if let Some(csp) = &csp_header {
+                                        builder = builder.header("Content-Security-Policy", csp.as_str());
+                                    }
+                                    builder.body(Cow::Owned(response.body)).expect("Failed to build host-handled response")
+                                }
+                                Err(_) => {
+                                    pending_protocol_requests.lock().unwrap().remove(&request_id);
+                                    Response::builder()
+                                        .status(500)
+                                        .body(Cow::Borrowed("Protocol handler disconnected".as_bytes()))
+                                        .expect("Failed to build 500 response")
+                                }
+                            };
+                            responder.respond(response);
+                        });
+                        return;
+                    }
+
+                    if !asset_bundle_for_protocol.is_empty() {
+                        let path = request.uri().path().to_string();
+                        let response = match asset_bundle_for_protocol.get(&path) {
+                            Some((content_type, compressed)) => {
+                                let mut decompressed = Vec::new();
+                                brotli::Decompressor::new(compressed.as_slice(), 4096)
+                                    .read_to_end(&mut decompressed)
+                                    .expect("Failed to decompress bundled asset");
+                                let file_len = decompressed.len() as u64;
+
+                                let range_header = request.headers()
+                                    .get("Range")
+                                    .and_then(|v| v.to_str().ok());
+
+                                match range_header {
+                                    Some(range) => match parse_range(range, file_len) {
+                                        Ok((start, end)) => {
+                                            let slice = decompressed[start as usize..=end as usize].to_vec();
+                                            let mut builder = Response::builder()
+                                                .status(206)
+                                                .header("Content-Type", content_type.as_str())
+                                                .header("Content-Range", format!("bytes {}-{}/{}", start, end, file_len))
+                                                .header("Accept-Ranges", "bytes")
+                                                .header("Content-Length", slice.len().to_string());
+                                            if let Some(csp) = &csp_header {
+                                                builder = builder.header("Content-Security-Policy", csp.as_str());
+                                            }
+                                            builder.body(Cow::Owned(slice)).expect("Failed to build 206 response")
+                                        }
+                                        Err(_) => {
+                                            Response::builder()
+                                                .status(416)
+                                                .header("Content-Range", format!("bytes */{}", file_len))
+                                                .body(Cow::Borrowed("Range Not Satisfiable".as_bytes()))
+                                                .expect("Failed to build 416 response")
+                                        }
+                                    },
+                                    None => {
+                                        let mut builder = Response::builder()
+                                            .header("Content-Type", content_type.as_str())
+                                            .header("Accept-Ranges", "bytes");
+                                        if let Some(csp) = &csp_header {
+                                            builder = builder.header("Content-Security-Policy", csp.as_str());
+                                        }
+                                        builder.body(Cow::Owned(decompressed)).expect("Failed to build bundled response")
+                                    }
+                                }
+                            }
+                            None => {
+                                Response::builder()
+                                    .status(404)
+                                    .body(Cow::Borrowed("Not Found".as_bytes()))
+                                    .expect("Failed to build 404 response")
+                            }
+                        };
+                        responder.respond(response);
+                        return;
+                    }
+
                     let mut path = request.uri().path().to_string();
 
                     #[cfg(windows)]
@@ -100,20 +415,84 @@ impl ApplicationHandler<Request> for App {
                         path.remove(0);
                     }
 
-                    match fs::read(&path) {
-                        Ok(contents) => {
-                            Response::builder()
-                                .header("Content-Type", mime_type(&path))
-                                .body(Cow::Owned(contents))
-                                .expect("Failed to build response")
-                        }
+                    let metadata = match fs::metadata(&path) {
+                        Ok(metadata) => metadata,
                         Err(_) => {
-                            Response::builder()
-                                .status(404)
-                                .body(Cow::Borrowed("Not Found".as_bytes()))
-                                .expect("Failed to build 404 response")
+                            responder.respond(
+                                Response::builder()
+                                    .status(404)
+                                    .body(Cow::Borrowed("Not Found".as_bytes()))
+                                    .expect("Failed to build 404 response")
+                            );
+                            return;
                         }
-                    }
+                    };
+                    let file_len = metadata.len();
+
+                    let range_header = request.headers()
+                        .get("Range")
+                        .and_then(|v| v.to_str().ok());
+
+                    let response = match range_header {
+                        Some(range) => {
+                            match parse_range(range, file_len) {
+                                Ok((start, end)) => {
+                                    let slice_len = (end - start + 1) as usize;
+                                    // The file may have been deleted, truncated or rotated since the
+                                    // `fs::metadata` call above, so fail this one request with a
+                                    // proper response instead of `.expect()`-ing the whole process down.
+                                    match read_range(&path, start, slice_len) {
+                                        Ok(buf) => {
+                                            let mut builder = Response::builder()
+                                                .status(206)
+                                                .header("Content-Type", mime_type(&path))
+                                                .header("Content-Range", format!("bytes {}-{}/{}", start, end, file_len))
+                                                .header("Accept-Ranges", "bytes")
+                                                .header("Content-Length", slice_len.to_string());
+                                            if let Some(csp) = &csp_header {
+                                                builder = builder.header("Content-Security-Policy", csp.as_str());
+                                            }
+                                            builder.body(Cow::Owned(buf)).expect("Failed to build 206 response")
+                                        }
+                                        Err(_) => {
+                                            Response::builder()
+                                                .status(404)
+                                                .body(Cow::Borrowed("Not Found".as_bytes()))
+                                                .expect("Failed to build 404 response")
+                                        }
+                                    }
+                                }
+                                Err(_) => {
+                                    Response::builder()
+                                        .status(416)
+                                        .header("Content-Range", format!("bytes */{}", file_len))
+                                        .body(Cow::Borrowed("Range Not Satisfiable".as_bytes()))
+                                        .expect("Failed to build 416 response")
+                                }
+                            }
+                        }
+                        None => {
+                            match fs::read(&path) {
+                                Ok(contents) => {
+                                    let mut builder = Response::builder()
+                                        .header("Content-Type", mime_type(&path))
+                                        .header("Accept-Ranges", "bytes");
+                                    if let Some(csp) = &csp_header {
+                                        builder = builder.header("Content-Security-Policy", csp.as_str());
+                                    }
+                                    builder.body(Cow::Owned(contents)).expect("Failed to build response")
+                                }
+                                Err(_) => {
+                                    Response::builder()
+                                        .status(404)
+                                        .body(Cow::Borrowed("Not Found".as_bytes()))
+                                        .expect("Failed to build 404 response")
+                                }
+                            }
+                        }
+                    };
+
+                    responder.respond(response);
                 });
 
                 if let Some(script_path) = options.preload {
@@ -125,12 +504,79 @@ impl ApplicationHandler<Request> for App {
 
                 builder = builder.with_ipc_handler(move |msg| {
                     let window_id = id;
+                    let body = msg.body().to_string();
+
+                    let current_origin = origin.lock().unwrap().clone();
+                    if current_origin != "app://" && !allowed_origins.contains(&current_origin) {
+                        send_event(&EventOut::IPCBlocked { id: window_id, origin: current_origin });
+                        return;
+                    }
+
+                    if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&body) {
+                        if parsed.get("channel").and_then(|c| c.as_str()) == Some(SCRIPT_RESULT_CHANNEL) {
+                            let request_id = parsed.get("request_id").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+                            // Only forward a result for a request this exact window's host
+                            // actually issued — scoped by window so content in one window can't
+                            // forge (or steal) a result pending on another.
+                            if pending_script_requests.lock().unwrap().remove(&(window_id, request_id)) {
+                                send_event(&EventOut::ScriptResult {
+                                    id: window_id,
+                                    request_id,
+                                    result: ScriptOutcome {
+                                        ok: parsed.get("ok").and_then(|v| v.as_bool()).unwrap_or(false),
+                                        value: parsed.get("value").cloned(),
+                                        error: parsed.get("error").and_then(|v| v.as_str()).map(str::to_string)
+                                    }
+                                });
+                            }
+                            return;
+                        }
+                    }
+
                     send_event(&EventOut::IPCMessage {
                         id: window_id,
-                        body: msg.body().to_string() 
+                        body
                     });
                 });
 
+                // `with_navigation_handler` also fires for sub-frame/iframe
+                // navigations on WebKit-based backends, so it cannot be trusted
+                // to track the top-level origin: content from a blocked origin
+                // could embed `<iframe src="app://x">` to flip it and then slip
+                // its own postMessage past the IPC allowlist below. Only
+                // `with_on_page_load_handler`'s `Started` event reflects the
+                // main frame actually committing to a new location.
+                builder = builder.with_navigation_handler(move |_url| true);
+
+                let origin_for_navigation = self.window_origins[&id].clone();
+                builder = builder.with_on_page_load_handler(move |event, url| {
+                    if event == wry::PageLoadEvent::Started {
+                        *origin_for_navigation.lock().unwrap() = origin_of(&url);
+                    }
+                });
+
+                builder = builder.with_drag_drop_handler(move |event| {
+                    match event {
+                        wry::DragDropEvent::Enter { paths, .. } => {
+                            send_event(&EventOut::FileDropHover {
+                                id,
+                                paths: paths.iter().map(|p| p.display().to_string()).collect()
+                            });
+                        }
+                        wry::DragDropEvent::Drop { paths, .. } => {
+                            send_event(&EventOut::FileDrop {
+                                id,
+                                paths: paths.iter().map(|p| p.display().to_string()).collect()
+                            });
+                        }
+                        wry::DragDropEvent::Leave => {
+                            send_event(&EventOut::FileDropCancelled { id });
+                        }
+                        _ => {}
+                    }
+                    true
+                });
+
                 let webview: WebView = builder.build(&window).unwrap();
 
                 self.window_id_map.insert(window.id(), id);
@@ -141,12 +587,36 @@ impl ApplicationHandler<Request> for App {
             }
             Request::LoadUrl { id, url } => {
                 if let Some((_, webview)) = self.windows.get_mut(&id){
+                    if let Some(origin) = self.window_origins.get(&id) {
+                        *origin.lock().unwrap() = origin_of(&url);
+                    }
+                    if let Some(Some(template)) = self.window_csp_templates.get(&id) {
+                        let nonce = generate_nonce();
+                        let rendered = template.replace("{nonce}", &nonce);
+                        if let Some(csp) = self.window_csp.get(&id) {
+                            *csp.lock().unwrap() = Some(rendered);
+                        }
+                    }
                     webview.load_url(&url).unwrap();
                     send_event(&EventOut::ResponseOk { id });
                 }
             }
             Request::LoadHtml { id, html } => {
                 if let Some((_, webview)) = self.windows.get_mut(&id){
+                    if let Some(origin) = self.window_origins.get(&id) {
+                        *origin.lock().unwrap() = "app://".to_string();
+                    }
+                    let html = if let Some(Some(template)) = self.window_csp_templates.get(&id) {
+                        let nonce = generate_nonce();
+                        let rendered = template.replace("{nonce}", &nonce);
+                        if let Some(csp) = self.window_csp.get(&id) {
+                            *csp.lock().unwrap() = Some(rendered.clone());
+                        }
+                        let html = inject_nonce_into_html(&html, &nonce);
+                        inject_csp_meta(&html, &rendered)
+                    } else {
+                        html
+                    };
                     webview.load_html(&html).unwrap();
                     send_event(&EventOut::ResponseOk { id });
                 }
@@ -164,6 +634,18 @@ impl ApplicationHandler<Request> for App {
                     webview.evaluate_script(&script).unwrap();
                 }
             }
+            Request::EvaluateScript { id, request_id, script } => {
+                if let Some((_, webview)) = self.windows.get_mut(&id){
+                    self.pending_script_requests.lock().unwrap().insert((id, request_id));
+                    let shim = build_script_shim(request_id, &script);
+                    webview.evaluate_script(&shim).unwrap();
+                }
+            }
+            Request::ProtocolResponse { request_id, status, headers, body } => {
+                if let Some((_, sender)) = self.pending_protocol_requests.lock().unwrap().remove(&request_id) {
+                    let _ = sender.send(ProtocolResponsePayload { status, headers, body });
+                }
+            }
         }
     }
 
@@ -173,12 +655,42 @@ impl ApplicationHandler<Request> for App {
             window_id: winit::window::WindowId,
             event: WindowEvent,
         ) {
-        if let WindowEvent::CloseRequested = event {
-            // searching hashmap for window id and remove it
-            if let Some(id) = self.window_id_map.remove(&window_id){
-                self.windows.remove(&id);
-                send_event(&EventOut::WindowClosed { id });
+        match event {
+            WindowEvent::CloseRequested => {
+                // searching hashmap for window id and remove it
+                if let Some(id) = self.window_id_map.remove(&window_id){
+                    self.windows.remove(&id);
+                    self.window_origins.remove(&id);
+                    self.window_csp_templates.remove(&id);
+                    self.window_csp.remove(&id);
+                    // drop any in-flight host-handled protocol requests for this window so
+                    // their blocked responder threads see a closed channel and exit instead
+                    // of parking forever
+                    self.pending_protocol_requests.lock().unwrap().retain(|_, (win_id, _)| *win_id != id);
+                    send_event(&EventOut::WindowClosed { id });
+                }
+            }
+            WindowEvent::Resized(size) => {
+                if let Some(&id) = self.window_id_map.get(&window_id) {
+                    send_event(&EventOut::WindowResized { id, width: size.width, height: size.height });
+                }
+            }
+            WindowEvent::Moved(position) => {
+                if let Some(&id) = self.window_id_map.get(&window_id) {
+                    send_event(&EventOut::WindowMoved { id, x: position.x, y: position.y });
+                }
+            }
+            WindowEvent::Focused(focused) => {
+                if let Some(&id) = self.window_id_map.get(&window_id) {
+                    send_event(&EventOut::WindowFocused { id, focused });
+                }
+            }
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                if let Some(&id) = self.window_id_map.get(&window_id) {
+                    send_event(&EventOut::ScaleFactorChanged { id, scale: scale_factor });
+                }
             }
+            _ => {}
         }
     }
 }
@@ -211,7 +723,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut app = App {
         proxy,
         windows: HashMap::new(),
-        window_id_map: HashMap::new()
+        window_id_map: HashMap::new(),
+        pending_protocol_requests: Arc::new(Mutex::new(HashMap::new())),
+        next_protocol_request_id: Arc::new(AtomicU32::new(1)),
+        window_origins: HashMap::new(),
+        window_csp_templates: HashMap::new(),
+        window_csp: HashMap::new(),
+        pending_script_requests: Arc::new(Mutex::new(HashSet::new()))
     };
     event_loop.run_app(&mut app)?;
     Ok(())